@@ -0,0 +1,190 @@
+//! Python backend sidecar management
+//!
+//! Spawns the bundled Python backend through `tauri_plugin_shell` and
+//! supervises it: an unexpected exit emits `backend-status` and triggers an
+//! automatic restart with backoff, up to a fixed retry budget. Stdout lines
+//! that parse as a `{"event": ..., "payload": ...}` telemetry message are
+//! broadcast to subscribed overlays; anything else (plus all of stderr) is
+//! forwarded to the frontend as a `backend-log` event.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::overlay::{self, OverlayState};
+
+const SIDECAR_NAME: &str = "python-backend";
+const MAX_RESTARTS: u32 = 5;
+const RESTART_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Managed state for the Python sidecar process.
+#[derive(Default)]
+pub struct PythonProcess {
+    child: Mutex<Option<CommandChild>>,
+    supervisor_enabled: AtomicBool,
+    restart_count: AtomicU32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum BackendStatus {
+    Starting,
+    Healthy,
+    Crashed { restart_count: u32 },
+    Stopped,
+}
+
+fn emit_status(app: &AppHandle, status: BackendStatus) {
+    let _ = app.emit("backend-status", status);
+}
+
+/// A telemetry line from the Python backend: `broadcast_overlay_event`
+/// fans it out to whichever overlays are subscribed to `event`.
+#[derive(Deserialize)]
+struct BackendMessage {
+    event: String,
+    payload: serde_json::Value,
+}
+
+/// Start the Python backend sidecar and enable the restart supervisor.
+/// A no-op if a child is already running, so a double-click or a start
+/// racing an auto-restart doesn't orphan the previous process.
+#[tauri::command]
+pub fn start_backend(
+    app: AppHandle,
+    state: tauri::State<'_, PythonProcess>,
+) -> Result<String, String> {
+    if state.child.lock().map_err(|e| e.to_string())?.is_some() {
+        return Ok("Backend already running".to_string());
+    }
+
+    state.supervisor_enabled.store(true, Ordering::SeqCst);
+    state.restart_count.store(0, Ordering::SeqCst);
+    spawn_sidecar(app)?;
+    Ok("Backend started".to_string())
+}
+
+/// Disable the restart supervisor and kill the running backend, if any.
+#[tauri::command]
+pub fn stop_backend(state: tauri::State<'_, PythonProcess>) -> Result<String, String> {
+    state.supervisor_enabled.store(false, Ordering::SeqCst);
+
+    let mut child = state.child.lock().map_err(|e| e.to_string())?;
+    if let Some(child) = child.take() {
+        child.kill().map_err(|e| e.to_string())?;
+        return Ok("Backend stopped".to_string());
+    }
+
+    Ok("Backend was not running".to_string())
+}
+
+/// Kill the backend without touching the supervisor flag, used on app exit
+/// so no orphan Python process survives the Tauri process.
+pub fn reap(app: &AppHandle) {
+    let state = app.state::<PythonProcess>();
+    if let Ok(mut child) = state.child.lock() {
+        if let Some(child) = child.take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+fn spawn_sidecar(app: AppHandle) -> Result<(), String> {
+    let sidecar = app.shell().sidecar(SIDECAR_NAME).map_err(|e| e.to_string())?;
+    let (mut rx, child) = sidecar.spawn().map_err(|e| e.to_string())?;
+
+    {
+        let state = app.state::<PythonProcess>();
+        *state.child.lock().map_err(|e| e.to_string())? = Some(child);
+    }
+
+    emit_status(&app, BackendStatus::Starting);
+
+    tauri::async_runtime::spawn(async move {
+        let mut reported_healthy = false;
+
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => {
+                    if !reported_healthy {
+                        reported_healthy = true;
+                        // The restart budget is per crash-burst, not per
+                        // lifetime: a run that makes it to `Healthy` clears it.
+                        app.state::<PythonProcess>()
+                            .restart_count
+                            .store(0, Ordering::SeqCst);
+                        emit_status(&app, BackendStatus::Healthy);
+                    }
+
+                    match serde_json::from_slice::<BackendMessage>(&line) {
+                        Ok(message) => {
+                            let overlay_state = app.state::<OverlayState>();
+                            if let Err(err) = overlay::broadcast_overlay_event(
+                                &app,
+                                &overlay_state,
+                                &message.event,
+                                message.payload,
+                            ) {
+                                eprintln!("failed to broadcast backend event: {err}");
+                            }
+                        }
+                        Err(_) => {
+                            let _ =
+                                app.emit("backend-log", String::from_utf8_lossy(&line).into_owned());
+                        }
+                    }
+                }
+                CommandEvent::Stderr(line) => {
+                    let _ = app.emit("backend-log", String::from_utf8_lossy(&line).into_owned());
+                }
+                CommandEvent::Error(err) => {
+                    let _ = app.emit("backend-log", format!("error: {err}"));
+                }
+                CommandEvent::Terminated(_) => {
+                    on_terminated(app.clone());
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Called whenever the sidecar's event stream reports termination, whether
+/// from a crash or from `stop_backend` killing it intentionally.
+fn on_terminated(app: AppHandle) {
+    let state = app.state::<PythonProcess>();
+    *state.child.lock().unwrap() = None;
+
+    if !state.supervisor_enabled.load(Ordering::SeqCst) {
+        emit_status(&app, BackendStatus::Stopped);
+        return;
+    }
+
+    let restart_count = state.restart_count.fetch_add(1, Ordering::SeqCst) + 1;
+    emit_status(&app, BackendStatus::Crashed { restart_count });
+
+    if restart_count > MAX_RESTARTS {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESTART_BACKOFF * restart_count).await;
+
+        let state = app.state::<PythonProcess>();
+        // `start_backend` may have spawned a child during the backoff.
+        let child_running = state.child.lock().map(|c| c.is_some()).unwrap_or(true);
+        if state.supervisor_enabled.load(Ordering::SeqCst) && !child_running {
+            if let Err(err) = spawn_sidecar(app.clone()) {
+                eprintln!("failed to restart python backend: {err}");
+            }
+        }
+    });
+}
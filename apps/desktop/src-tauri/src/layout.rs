@@ -0,0 +1,230 @@
+//! Interactive overlay layout/edit mode
+//!
+//! While layout mode is active, overlays temporarily give up click-through
+//! so the user can grab them, and a window-moved listener snaps an
+//! overlay's edges flush to nearby monitor edges or other overlays' edges.
+//! On exit, each overlay's real on-screen position/size is read back into
+//! `OverlayState.configs` and its prior click-through flag is restored.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewWindow, WindowEvent};
+
+use crate::overlay::OverlayState;
+
+const SNAP_THRESHOLD: i32 = 12;
+
+/// Tracks whether layout mode is active, which overlays had a snap listener
+/// wired up already, and the click-through flag each overlay had before
+/// entering layout mode so it can be restored on exit.
+#[derive(Default)]
+pub struct LayoutState {
+    active: AtomicBool,
+    wired: Mutex<HashSet<String>>,
+    prior_click_through: Mutex<HashMap<String, bool>>,
+}
+
+/// Forget that `overlay_id`'s snap listener was already wired up, so it
+/// gets registered again the next time its window is created. Call this
+/// when an overlay's window is closed/destroyed.
+pub fn forget_wired(app: &AppHandle, overlay_id: &str) {
+    if let Some(layout) = app.try_state::<LayoutState>() {
+        if let Ok(mut wired) = layout.wired.lock() {
+            wired.remove(overlay_id);
+        }
+    }
+}
+
+/// Enter layout mode: every overlay becomes grabbable (click-through is
+/// disabled) and starts snapping to nearby edges while it's dragged.
+#[tauri::command]
+pub fn enter_layout_mode(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+    layout: tauri::State<'_, LayoutState>,
+) -> Result<(), String> {
+    layout.active.store(true, Ordering::SeqCst);
+
+    let configs = state.configs.lock().map_err(|e| e.to_string())?.clone();
+    let mut prior = layout.prior_click_through.lock().map_err(|e| e.to_string())?;
+    prior.clear();
+
+    for (overlay_id, config) in configs {
+        let window_label = format!("overlay_{}", overlay_id);
+        let Some(window) = app.get_webview_window(&window_label) else {
+            continue;
+        };
+
+        prior.insert(overlay_id.clone(), config.click_through);
+
+        #[cfg(target_os = "windows")]
+        let _ = window.set_ignore_cursor_events(false);
+
+        let mut wired = layout.wired.lock().map_err(|e| e.to_string())?;
+        if wired.insert(overlay_id.clone()) {
+            register_snap_listener(app.clone(), window, overlay_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Exit layout mode: read back each overlay's real position/size into
+/// `OverlayState.configs` and restore the click-through flag it had before
+/// `enter_layout_mode`.
+#[tauri::command]
+pub fn exit_layout_mode(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+    layout: tauri::State<'_, LayoutState>,
+) -> Result<(), String> {
+    layout.active.store(false, Ordering::SeqCst);
+
+    let prior = {
+        let mut prior = layout.prior_click_through.lock().map_err(|e| e.to_string())?;
+        std::mem::take(&mut *prior)
+    };
+
+    {
+        let mut configs = state.configs.lock().map_err(|e| e.to_string())?;
+        for (overlay_id, click_through) in &prior {
+            let window_label = format!("overlay_{}", overlay_id);
+            let Some(window) = app.get_webview_window(&window_label) else {
+                continue;
+            };
+
+            if let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) {
+                if let Some(config) = configs.get_mut(overlay_id) {
+                    config.x = position.x;
+                    config.y = position.y;
+                    config.width = size.width;
+                    config.height = size.height;
+                    config.click_through = *click_through;
+                    // The user just placed this overlay by hand; drop the
+                    // anchor so the read-back position isn't immediately
+                    // re-resolved away on the next create/update.
+                    config.anchor = None;
+                }
+            }
+
+            #[cfg(target_os = "windows")]
+            let _ = window.set_ignore_cursor_events(*click_through);
+        }
+    }
+
+    crate::persistence::schedule_save(app);
+
+    Ok(())
+}
+
+/// Start a native window drag for an overlay, invoked from its drag handle
+/// on mousedown.
+#[tauri::command]
+pub fn start_overlay_drag(app: AppHandle, overlay_id: String) -> Result<(), String> {
+    let window_label = format!("overlay_{}", overlay_id);
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| format!("Overlay '{}' not found", overlay_id))?;
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+fn register_snap_listener(app: AppHandle, window: WebviewWindow, overlay_id: String) {
+    window.on_window_event(move |event| {
+        let WindowEvent::Moved(position) = event else {
+            return;
+        };
+
+        let layout = app.state::<LayoutState>();
+        if !layout.active.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let window_label = format!("overlay_{}", overlay_id);
+        let Some(window) = app.get_webview_window(&window_label) else {
+            return;
+        };
+        let Ok(size) = window.outer_size() else {
+            return;
+        };
+
+        let (snapped_x, snapped_y) = snap_position(&app, &overlay_id, *position, size);
+        if snapped_x != position.x || snapped_y != position.y {
+            let _ = window.set_position(tauri::Position::Physical(PhysicalPosition {
+                x: snapped_x,
+                y: snapped_y,
+            }));
+        }
+    });
+}
+
+/// Snap `pos` flush to a nearby monitor edge or another overlay's edge, if
+/// any edge is within [`SNAP_THRESHOLD`] pixels.
+fn snap_position(
+    app: &AppHandle,
+    overlay_id: &str,
+    pos: tauri::PhysicalPosition<i32>,
+    size: tauri::PhysicalSize<u32>,
+) -> (i32, i32) {
+    let mut edges_x = Vec::new();
+    let mut edges_y = Vec::new();
+
+    if let Ok(monitors) = app.available_monitors() {
+        for monitor in monitors {
+            let mp = monitor.position();
+            let ms = monitor.size();
+            edges_x.push(mp.x);
+            edges_x.push(mp.x + ms.width as i32);
+            edges_y.push(mp.y);
+            edges_y.push(mp.y + ms.height as i32);
+        }
+    }
+
+    if let Some(overlay_state) = app.try_state::<OverlayState>() {
+        if let Ok(configs) = overlay_state.configs.lock() {
+            for (id, config) in configs.iter() {
+                if id == overlay_id {
+                    continue;
+                }
+
+                let (ox, oy, ow, oh) = app
+                    .get_webview_window(&format!("overlay_{}", id))
+                    .and_then(|w| w.outer_position().ok().zip(w.outer_size().ok()))
+                    .map(|(p, s)| (p.x, p.y, s.width as i32, s.height as i32))
+                    .unwrap_or((config.x, config.y, config.width as i32, config.height as i32));
+
+                edges_x.push(ox);
+                edges_x.push(ox + ow);
+                edges_y.push(oy);
+                edges_y.push(oy + oh);
+            }
+        }
+    }
+
+    let left = pos.x;
+    let right = pos.x + size.width as i32;
+    let top = pos.y;
+    let bottom = pos.y + size.height as i32;
+
+    let mut x = pos.x;
+    let mut y = pos.y;
+
+    for edge in &edges_x {
+        if (left - edge).abs() <= SNAP_THRESHOLD {
+            x = *edge;
+        } else if (right - edge).abs() <= SNAP_THRESHOLD {
+            x = edge - size.width as i32;
+        }
+    }
+
+    for edge in &edges_y {
+        if (top - edge).abs() <= SNAP_THRESHOLD {
+            y = *edge;
+        } else if (bottom - edge).abs() <= SNAP_THRESHOLD {
+            y = edge - size.height as i32;
+        }
+    }
+
+    (x, y)
+}
@@ -1,36 +1,25 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod backend;
+mod layout;
+mod monitors;
 mod overlay;
+mod persistence;
 
-use std::process::{Child, Command};
-use std::sync::Mutex;
-use tauri::Manager;
+use tauri::{Manager, RunEvent};
 use tauri_plugin_updater::UpdaterExt;
 
+use backend::{start_backend, stop_backend, PythonProcess};
+use layout::{enter_layout_mode, exit_layout_mode, start_overlay_drag, LayoutState};
+use monitors::get_monitors;
 use overlay::{
-    apply_overlay_preset, close_overlay, create_overlay, get_overlay_configs, get_overlay_presets,
+    apply_overlay_preset, close_overlay, create_overlay, emit_overlay_data, get_active_layers,
+    get_overlay_configs, get_overlay_presets, pop_overlay_layer, push_overlay_layer,
     save_overlay_preset, set_overlay_click_through, toggle_all_overlays, toggle_overlay,
     update_overlay_config, OverlayState,
 };
-
-struct PythonProcess(Mutex<Option<Child>>);
-
-#[tauri::command]
-fn start_backend() -> Result<String, String> {
-    // TODO: Start Python backend as sidecar
-    Ok("Backend started".to_string())
-}
-
-#[tauri::command]
-fn stop_backend(state: tauri::State<PythonProcess>) -> Result<String, String> {
-    let mut process = state.0.lock().map_err(|e| e.to_string())?;
-    if let Some(mut child) = process.take() {
-        child.kill().map_err(|e| e.to_string())?;
-        return Ok("Backend stopped".to_string());
-    }
-    Ok("Backend was not running".to_string())
-}
+use persistence::{save_overlay_state, PersistenceState};
 
 #[tauri::command]
 fn get_current_version() -> String {
@@ -38,13 +27,15 @@ fn get_current_version() -> String {
 }
 
 fn main() {
-    tauri::Builder::default()
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
-        .manage(PythonProcess(Mutex::new(None)))
+        .manage(PythonProcess::default())
         .manage(OverlayState::default())
+        .manage(PersistenceState::default())
+        .manage(LayoutState::default())
         .invoke_handler(tauri::generate_handler![
             start_backend,
             stop_backend,
@@ -60,6 +51,18 @@ fn main() {
             apply_overlay_preset,
             save_overlay_preset,
             set_overlay_click_through,
+            emit_overlay_data,
+            push_overlay_layer,
+            pop_overlay_layer,
+            get_active_layers,
+            // Persistence commands
+            save_overlay_state,
+            // Layout mode commands
+            enter_layout_mode,
+            exit_layout_mode,
+            start_overlay_drag,
+            // Monitor commands
+            get_monitors,
         ])
         .setup(|app| {
             // Open devtools in debug mode
@@ -69,6 +72,11 @@ fn main() {
                 window.open_devtools();
             }
 
+            // Restore overlay configs/presets persisted from a previous run
+            if let Err(err) = persistence::load_and_merge(app.handle(), &app.state::<OverlayState>()) {
+                eprintln!("failed to load persisted overlay state: {err}");
+            }
+
             // Register global hotkeys
             // F1 = Toggle all overlays
             // F2 = Toggle telemetry overlay
@@ -77,6 +85,12 @@ fn main() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    app.run(|app_handle, event| {
+        if let RunEvent::Exit = event {
+            backend::reap(app_handle);
+        }
+    });
 }
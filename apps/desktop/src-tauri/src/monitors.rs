@@ -0,0 +1,173 @@
+//! Multi-monitor aware overlay placement
+//!
+//! Overlay positions can be expressed as an [`OverlayAnchor`] — a target
+//! monitor plus a corner and margin fractions of that monitor's size —
+//! instead of raw pixels, so a layout survives a different resolution or
+//! monitor arrangement than the one it was authored on. `resolve_anchor`
+//! turns an anchor into physical coordinates and `clamp_to_monitor` pulls a
+//! position back on-screen if it no longer fits any monitor at all.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Monitor};
+
+/// Which corner (or center) of a monitor's full bounds an overlay is pinned
+/// to. Tauri doesn't expose the OS work area (the region excluding the
+/// taskbar/dock), so a `Bottom*` anchor with a small margin can land behind
+/// it; pick a larger margin if that matters for your layout.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// A resolution-independent overlay position: a monitor id (or `"primary"`)
+/// plus a corner and a margin expressed as a fraction of that monitor's
+/// width/height.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayAnchor {
+    pub monitor: String,
+    pub corner: AnchorCorner,
+    pub margin_x_frac: f64,
+    pub margin_y_frac: f64,
+}
+
+/// A monitor's bounds and scale factor, as reported to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub id: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+/// List the available monitors with a stable id each [`OverlayAnchor`] can
+/// reference.
+#[tauri::command]
+pub fn get_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let position = monitor.position();
+            let size = monitor.size();
+            MonitorInfo {
+                id: monitor_id(monitor, index),
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                scale_factor: monitor.scale_factor(),
+            }
+        })
+        .collect())
+}
+
+fn monitor_id(monitor: &Monitor, index: usize) -> String {
+    monitor
+        .name()
+        .cloned()
+        .unwrap_or_else(|| format!("monitor-{index}"))
+}
+
+fn find_monitor(app: &AppHandle, id: &str) -> Option<Monitor> {
+    if id == "primary" {
+        if let Ok(Some(monitor)) = app.primary_monitor() {
+            return Some(monitor);
+        }
+    }
+
+    let monitors = app.available_monitors().ok()?;
+    monitors
+        .into_iter()
+        .enumerate()
+        .find_map(|(index, monitor)| (monitor_id(&monitor, index) == id).then_some(monitor))
+}
+
+/// Resolve an [`OverlayAnchor`] to a physical `(x, y)` position for a window
+/// of the given size. Returns `None` if the anchor's monitor can't be found.
+pub fn resolve_anchor(
+    app: &AppHandle,
+    anchor: &OverlayAnchor,
+    width: u32,
+    height: u32,
+) -> Option<(i32, i32)> {
+    let monitor = find_monitor(app, &anchor.monitor)?;
+    let monitor_pos = monitor.position();
+    let monitor_size = monitor.size();
+
+    let margin_x = (anchor.margin_x_frac * monitor_size.width as f64) as i32;
+    let margin_y = (anchor.margin_y_frac * monitor_size.height as f64) as i32;
+    let (width, height) = (width as i32, height as i32);
+
+    let (x, y) = match anchor.corner {
+        AnchorCorner::TopLeft => (monitor_pos.x + margin_x, monitor_pos.y + margin_y),
+        AnchorCorner::TopRight => (
+            monitor_pos.x + monitor_size.width as i32 - width - margin_x,
+            monitor_pos.y + margin_y,
+        ),
+        AnchorCorner::BottomLeft => (
+            monitor_pos.x + margin_x,
+            monitor_pos.y + monitor_size.height as i32 - height - margin_y,
+        ),
+        AnchorCorner::BottomRight => (
+            monitor_pos.x + monitor_size.width as i32 - width - margin_x,
+            monitor_pos.y + monitor_size.height as i32 - height - margin_y,
+        ),
+        AnchorCorner::Center => (
+            monitor_pos.x + (monitor_size.width as i32 - width) / 2 + margin_x,
+            monitor_pos.y + (monitor_size.height as i32 - height) / 2 + margin_y,
+        ),
+    };
+
+    Some((x, y))
+}
+
+/// Pull `(x, y)` back onto the nearest monitor if it doesn't fit on any of
+/// them, so a saved position that no longer matches the current monitor
+/// layout lands on-screen instead of vanishing.
+pub fn clamp_to_monitor(app: &AppHandle, x: i32, y: i32, width: u32, height: u32) -> (i32, i32) {
+    let Ok(monitors) = app.available_monitors() else {
+        return (x, y);
+    };
+
+    let fits = |monitor: &Monitor| {
+        let mp = monitor.position();
+        let ms = monitor.size();
+        x >= mp.x
+            && y >= mp.y
+            && x + width as i32 <= mp.x + ms.width as i32
+            && y + height as i32 <= mp.y + ms.height as i32
+    };
+
+    if monitors.iter().any(fits) {
+        return (x, y);
+    }
+
+    let Some(target) = monitors.iter().min_by_key(|m| center_distance(m, x, y)) else {
+        return (x, y);
+    };
+
+    let mp = target.position();
+    let ms = target.size();
+    let clamped_x = x.max(mp.x).min(mp.x + ms.width as i32 - width as i32);
+    let clamped_y = y.max(mp.y).min(mp.y + ms.height as i32 - height as i32);
+    (clamped_x, clamped_y)
+}
+
+fn center_distance(monitor: &Monitor, x: i32, y: i32) -> i64 {
+    let mp = monitor.position();
+    let ms = monitor.size();
+    let cx = mp.x + ms.width as i32 / 2;
+    let cy = mp.y + ms.height as i32 / 2;
+    let dx = (cx - x) as i64;
+    let dy = (cy - y) as i64;
+    dx * dx + dy * dy
+}
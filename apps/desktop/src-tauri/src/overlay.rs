@@ -4,9 +4,11 @@
 //! that display telemetry, strategy, and standings information over the game.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::monitors::{self, AnchorCorner, OverlayAnchor};
 
 /// Overlay window configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +23,10 @@ pub struct OverlayConfig {
     pub opacity: f64,
     pub always_on_top: bool,
     pub click_through: bool,
+    /// Resolution-independent placement; when set, `x`/`y` are recomputed
+    /// from this anchor at create/update time instead of being used as-is.
+    #[serde(default)]
+    pub anchor: Option<OverlayAnchor>,
 }
 
 impl Default for OverlayConfig {
@@ -36,6 +42,7 @@ impl Default for OverlayConfig {
             opacity: 1.0,
             always_on_top: true,
             click_through: false,
+            anchor: None,
         }
     }
 }
@@ -51,6 +58,12 @@ pub struct OverlayPreset {
 pub struct OverlayState {
     pub configs: Mutex<HashMap<String, OverlayConfig>>,
     pub presets: Mutex<Vec<OverlayPreset>>,
+    /// Event names each overlay id cares about, so a broadcast doesn't wake
+    /// up e.g. the standings overlay with a telemetry tick.
+    pub subscriptions: Mutex<HashMap<String, HashSet<String>>>,
+    /// Ordered stack of active preset names. The visible overlay set and
+    /// each overlay's config is the fold of this stack bottom-to-top.
+    pub layers: Mutex<Vec<String>>,
 }
 
 impl Default for OverlayState {
@@ -71,6 +84,7 @@ impl Default for OverlayState {
                 opacity: 0.9,
                 always_on_top: true,
                 click_through: true,
+                anchor: None,
             },
         );
 
@@ -87,6 +101,7 @@ impl Default for OverlayState {
                 opacity: 0.9,
                 always_on_top: true,
                 click_through: true,
+                anchor: None,
             },
         );
 
@@ -103,9 +118,37 @@ impl Default for OverlayState {
                 opacity: 0.9,
                 always_on_top: true,
                 click_through: true,
+                anchor: None,
             },
         );
 
+        // Anchors resolve relative to the primary monitor's full bounds, so
+        // these presets hold up across different resolutions/monitor setups.
+        let top_left_anchor = || {
+            Some(OverlayAnchor {
+                monitor: "primary".to_string(),
+                corner: AnchorCorner::TopLeft,
+                margin_x_frac: 0.02,
+                margin_y_frac: 0.04,
+            })
+        };
+        let lower_left_anchor = || {
+            Some(OverlayAnchor {
+                monitor: "primary".to_string(),
+                corner: AnchorCorner::TopLeft,
+                margin_x_frac: 0.02,
+                margin_y_frac: 0.2,
+            })
+        };
+        let top_right_anchor = || {
+            Some(OverlayAnchor {
+                monitor: "primary".to_string(),
+                corner: AnchorCorner::TopRight,
+                margin_x_frac: 0.02,
+                margin_y_frac: 0.04,
+            })
+        };
+
         // Default presets
         let presets = vec![
             OverlayPreset {
@@ -114,22 +157,19 @@ impl Default for OverlayState {
                     OverlayConfig {
                         id: "telemetry".to_string(),
                         visible: true,
-                        x: 50,
-                        y: 50,
+                        anchor: top_left_anchor(),
                         ..Default::default()
                     },
                     OverlayConfig {
                         id: "strategy".to_string(),
                         visible: true,
-                        x: 50,
-                        y: 250,
+                        anchor: lower_left_anchor(),
                         ..Default::default()
                     },
                     OverlayConfig {
                         id: "standings".to_string(),
                         visible: true,
-                        x: 1600,
-                        y: 50,
+                        anchor: top_right_anchor(),
                         ..Default::default()
                     },
                 ],
@@ -140,15 +180,13 @@ impl Default for OverlayState {
                     OverlayConfig {
                         id: "telemetry".to_string(),
                         visible: true,
-                        x: 50,
-                        y: 50,
+                        anchor: top_left_anchor(),
                         ..Default::default()
                     },
                     OverlayConfig {
                         id: "standings".to_string(),
                         visible: true,
-                        x: 1600,
-                        y: 50,
+                        anchor: top_right_anchor(),
                         ..Default::default()
                     },
                 ],
@@ -158,16 +196,37 @@ impl Default for OverlayState {
                 overlays: vec![OverlayConfig {
                     id: "telemetry".to_string(),
                     visible: true,
-                    x: 50,
-                    y: 50,
+                    anchor: top_left_anchor(),
                     ..Default::default()
                 }],
             },
         ];
 
+        let mut subscriptions = HashMap::new();
+        subscriptions.insert(
+            "telemetry".to_string(),
+            ["lap_time", "fuel", "tyre_temps", "delta"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+        subscriptions.insert(
+            "strategy".to_string(),
+            ["delta", "fuel", "strategy_update"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+        subscriptions.insert(
+            "standings".to_string(),
+            ["standings"].into_iter().map(String::from).collect(),
+        );
+
         Self {
             configs: Mutex::new(configs),
             presets: Mutex::new(presets),
+            subscriptions: Mutex::new(subscriptions),
+            layers: Mutex::new(Vec::new()),
         }
     }
 }
@@ -184,13 +243,15 @@ pub async fn create_overlay(
         configs.get(&overlay_id).cloned()
     };
 
-    let config = config.ok_or_else(|| format!("Overlay '{}' not found", overlay_id))?;
+    let mut config = config.ok_or_else(|| format!("Overlay '{}' not found", overlay_id))?;
 
     // Check if window already exists
     if app.get_webview_window(&format!("overlay_{}", overlay_id)).is_some() {
         return Ok(());
     }
 
+    resolve_placement(&app, &mut config);
+
     // Build the overlay window
     let url = WebviewUrl::App(format!("/overlay/{}", overlay_id).into());
 
@@ -217,15 +278,46 @@ pub async fn create_overlay(
         let _ = window.set_ignore_cursor_events(true);
     }
 
+    // The builder's `position` is in logical units; place the window at the
+    // resolved physical position.
+    let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+        x: config.x,
+        y: config.y,
+    }));
+
+    // Store the resolved physical position so later reads (and layout mode)
+    // see where the window actually landed, not the unresolved anchor.
+    let mut configs = state.configs.lock().map_err(|e| e.to_string())?;
+    if let Some(stored) = configs.get_mut(&overlay_id) {
+        stored.x = config.x;
+        stored.y = config.y;
+    }
+
     Ok(())
 }
 
+/// Resolve `config`'s anchor (if any) to a physical position, then clamp
+/// the result onto a real monitor so it can't land off-screen.
+fn resolve_placement(app: &AppHandle, config: &mut OverlayConfig) {
+    if let Some(anchor) = &config.anchor {
+        if let Some((x, y)) = monitors::resolve_anchor(app, anchor, config.width, config.height) {
+            config.x = x;
+            config.y = y;
+        }
+    }
+
+    let (x, y) = monitors::clamp_to_monitor(app, config.x, config.y, config.width, config.height);
+    config.x = x;
+    config.y = y;
+}
+
 /// Close an overlay window
 #[tauri::command]
 pub async fn close_overlay(app: AppHandle, overlay_id: String) -> Result<(), String> {
     if let Some(window) = app.get_webview_window(&format!("overlay_{}", overlay_id)) {
         window.close().map_err(|e| e.to_string())?;
     }
+    crate::layout::forget_wired(&app, &overlay_id);
     Ok(())
 }
 
@@ -291,8 +383,10 @@ pub async fn toggle_all_overlays(
 pub async fn update_overlay_config(
     app: AppHandle,
     state: tauri::State<'_, OverlayState>,
-    config: OverlayConfig,
+    mut config: OverlayConfig,
 ) -> Result<(), String> {
+    resolve_placement(&app, &mut config);
+
     // Update stored config
     {
         let mut configs = state.configs.lock().map_err(|e| e.to_string())?;
@@ -332,6 +426,8 @@ pub async fn update_overlay_config(
         }
     }
 
+    crate::persistence::schedule_save(app);
+
     Ok(())
 }
 
@@ -382,27 +478,139 @@ pub async fn apply_overlay_preset(
     Ok(())
 }
 
+/// Fold the layer stack bottom-to-top: a higher layer overrides the config
+/// of any overlay id it defines, and lower layers still supply ids it
+/// doesn't mention.
+fn fold_layers(presets: &[OverlayPreset], stack: &[String]) -> HashMap<String, OverlayConfig> {
+    let mut resolved = HashMap::new();
+
+    for layer_name in stack {
+        if let Some(preset) = presets.iter().find(|p| &p.name == layer_name) {
+            for overlay in &preset.overlays {
+                resolved.insert(overlay.id.clone(), overlay.clone());
+            }
+        }
+    }
+
+    resolved
+}
+
+/// Apply the current layer stack's folded result to every known overlay:
+/// overlays in the fold get the resolved config (creating their window if
+/// visible), overlays the fold doesn't mention are hidden.
+async fn apply_resolved_layers(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+) -> Result<(), String> {
+    let resolved = {
+        let stack = state.layers.lock().map_err(|e| e.to_string())?;
+        let presets = state.presets.lock().map_err(|e| e.to_string())?;
+        fold_layers(&presets, &stack)
+    };
+
+    let known_ids: Vec<String> = {
+        let configs = state.configs.lock().map_err(|e| e.to_string())?;
+        configs.keys().cloned().collect()
+    };
+
+    for overlay_id in known_ids {
+        match resolved.get(&overlay_id) {
+            Some(layer_config) => {
+                let mut config = layer_config.clone();
+                config.id = overlay_id.clone();
+                update_overlay_config(app.clone(), state.clone(), config.clone()).await?;
+
+                if config.visible {
+                    create_overlay(app.clone(), state.clone(), overlay_id).await?;
+                }
+            }
+            None => {
+                let window_label = format!("overlay_{}", overlay_id);
+                if let Some(window) = app.get_webview_window(&window_label) {
+                    window.hide().map_err(|e| e.to_string())?;
+                }
+
+                let mut configs = state.configs.lock().map_err(|e| e.to_string())?;
+                if let Some(config) = configs.get_mut(&overlay_id) {
+                    config.visible = false;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Push a preset as a layer on top of the active stack and apply the fold.
+#[tauri::command]
+pub async fn push_overlay_layer(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+    layer_name: String,
+) -> Result<(), String> {
+    {
+        let presets = state.presets.lock().map_err(|e| e.to_string())?;
+        if !presets.iter().any(|p| p.name == layer_name) {
+            return Err(format!("Preset '{}' not found", layer_name));
+        }
+    }
+
+    {
+        let mut layers = state.layers.lock().map_err(|e| e.to_string())?;
+        layers.push(layer_name);
+    }
+
+    apply_resolved_layers(app, state).await
+}
+
+/// Pop the topmost layer and re-apply the fold of what remains, restoring
+/// the exact state the stack would have resolved to without that layer.
+#[tauri::command]
+pub async fn pop_overlay_layer(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+) -> Result<(), String> {
+    {
+        let mut layers = state.layers.lock().map_err(|e| e.to_string())?;
+        layers.pop();
+    }
+
+    apply_resolved_layers(app, state).await
+}
+
+/// Get the current stack of active layer names, bottom to top.
+#[tauri::command]
+pub fn get_active_layers(state: tauri::State<'_, OverlayState>) -> Result<Vec<String>, String> {
+    let layers = state.layers.lock().map_err(|e| e.to_string())?;
+    Ok(layers.clone())
+}
+
 /// Save current overlay positions as a preset
 #[tauri::command]
 pub fn save_overlay_preset(
+    app: AppHandle,
     state: tauri::State<'_, OverlayState>,
     preset_name: String,
 ) -> Result<(), String> {
-    let configs = state.configs.lock().map_err(|e| e.to_string())?;
-    let mut presets = state.presets.lock().map_err(|e| e.to_string())?;
+    {
+        let configs = state.configs.lock().map_err(|e| e.to_string())?;
+        let mut presets = state.presets.lock().map_err(|e| e.to_string())?;
 
-    let new_preset = OverlayPreset {
-        name: preset_name.clone(),
-        overlays: configs.values().cloned().collect(),
-    };
+        let new_preset = OverlayPreset {
+            name: preset_name.clone(),
+            overlays: configs.values().cloned().collect(),
+        };
 
-    // Replace existing preset with same name or add new
-    if let Some(existing) = presets.iter_mut().find(|p| p.name == preset_name) {
-        *existing = new_preset;
-    } else {
-        presets.push(new_preset);
+        // Replace existing preset with same name or add new
+        if let Some(existing) = presets.iter_mut().find(|p| p.name == preset_name) {
+            *existing = new_preset;
+        } else {
+            presets.push(new_preset);
+        }
     }
 
+    crate::persistence::schedule_save(app);
+
     Ok(())
 }
 
@@ -435,3 +643,63 @@ pub async fn set_overlay_click_through(
 
     Ok(())
 }
+
+/// Push a typed payload to one specific overlay by id. A no-op if that
+/// overlay's window doesn't exist or is currently hidden.
+#[tauri::command]
+pub fn emit_overlay_data(
+    app: AppHandle,
+    overlay_id: String,
+    event: String,
+    payload: serde_json::Value,
+) -> Result<(), String> {
+    emit_to_overlay(&app, &overlay_id, &event, payload)
+}
+
+/// Broadcast `event` to every overlay that is subscribed to it and
+/// currently visible. Data sources (telemetry ingestion, strategy
+/// calculations, standings updates) call this without needing to know
+/// which overlays exist or whether anything is listening.
+pub fn broadcast_overlay_event(
+    app: &AppHandle,
+    state: &OverlayState,
+    event: &str,
+    payload: serde_json::Value,
+) -> Result<(), String> {
+    let subscribed_ids: Vec<String> = {
+        let subscriptions = state.subscriptions.lock().map_err(|e| e.to_string())?;
+        subscriptions
+            .iter()
+            .filter(|(_, events)| events.contains(event))
+            .map(|(id, _)| id.clone())
+            .collect()
+    };
+
+    for overlay_id in subscribed_ids {
+        emit_to_overlay(app, &overlay_id, event, payload.clone())?;
+    }
+
+    Ok(())
+}
+
+/// Emit `event`/`payload` to `overlay_{overlay_id}` if that window exists
+/// and is currently visible. Hidden overlays are left alone so they aren't
+/// woken up by data they can't display.
+fn emit_to_overlay(
+    app: &AppHandle,
+    overlay_id: &str,
+    event: &str,
+    payload: serde_json::Value,
+) -> Result<(), String> {
+    let window_label = format!("overlay_{}", overlay_id);
+    let Some(window) = app.get_webview_window(&window_label) else {
+        return Ok(());
+    };
+
+    if !window.is_visible().unwrap_or(false) {
+        return Ok(());
+    }
+
+    app.emit_to(&window_label, event, payload)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,180 @@
+//! Disk persistence for overlay configs and presets
+//!
+//! Mirrors the design of `tauri-plugin-window-state`: a [`StateFlags`] bitmask
+//! selects which overlay properties are restored on the next launch, and
+//! writes triggered by config/preset edits are debounced so dragging an
+//! overlay around doesn't thrash the disk.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::overlay::{OverlayConfig, OverlayPreset, OverlayState};
+
+const STATE_FILENAME: &str = "overlay_state.json";
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+bitflags! {
+    /// Which overlay properties are restored on launch. Every property is
+    /// written to disk regardless; the flags only filter what is restored.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct StateFlags: u32 {
+        const POSITION = 1 << 0;
+        const SIZE = 1 << 1;
+        const VISIBILITY = 1 << 2;
+        const OPACITY = 1 << 3;
+        const ALWAYS_ON_TOP = 1 << 4;
+        const CLICK_THROUGH = 1 << 5;
+    }
+}
+
+/// Tracks the active restore flags and coordinates debounced writes.
+pub struct PersistenceState {
+    flags: Mutex<StateFlags>,
+    generation: AtomicU32,
+}
+
+impl Default for PersistenceState {
+    fn default() -> Self {
+        Self {
+            flags: Mutex::new(StateFlags::all()),
+            generation: AtomicU32::new(0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedState {
+    flags: u32,
+    configs: HashMap<String, OverlayConfig>,
+    presets: Vec<OverlayPreset>,
+}
+
+fn state_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(STATE_FILENAME))
+}
+
+fn write_state(app: &AppHandle, overlay_state: &OverlayState, flags: StateFlags) -> Result<(), String> {
+    let configs = overlay_state.configs.lock().map_err(|e| e.to_string())?.clone();
+    let presets = overlay_state.presets.lock().map_err(|e| e.to_string())?.clone();
+
+    let persisted = PersistedState {
+        flags: flags.bits(),
+        configs,
+        presets,
+    };
+
+    let path = state_file_path(app)?;
+    let json = serde_json::to_vec_pretty(&persisted).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Overwrite `current` with the fields selected by `flags` from `saved`.
+fn apply_flags(current: &mut OverlayConfig, saved: &OverlayConfig, flags: StateFlags) {
+    if flags.contains(StateFlags::POSITION) {
+        current.x = saved.x;
+        current.y = saved.y;
+    }
+    if flags.contains(StateFlags::SIZE) {
+        current.width = saved.width;
+        current.height = saved.height;
+    }
+    if flags.contains(StateFlags::VISIBILITY) {
+        current.visible = saved.visible;
+    }
+    if flags.contains(StateFlags::OPACITY) {
+        current.opacity = saved.opacity;
+    }
+    if flags.contains(StateFlags::ALWAYS_ON_TOP) {
+        current.always_on_top = saved.always_on_top;
+    }
+    if flags.contains(StateFlags::CLICK_THROUGH) {
+        current.click_through = saved.click_through;
+    }
+}
+
+/// Load the persisted state (if any) and merge it into `overlay_state`,
+/// restoring only the properties selected by the saved [`StateFlags`] for
+/// overlay ids that already exist; a saved id with no current config is
+/// inserted whole. Called once from `setup`, before any overlay window is created.
+pub fn load_and_merge(app: &AppHandle, overlay_state: &OverlayState) -> Result<(), String> {
+    let path = state_file_path(app)?;
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+    let persisted: PersistedState = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+    let flags = StateFlags::from_bits_truncate(persisted.flags);
+
+    {
+        let mut configs = overlay_state.configs.lock().map_err(|e| e.to_string())?;
+        for (id, saved) in &persisted.configs {
+            match configs.get_mut(id) {
+                Some(current) => apply_flags(current, saved, flags),
+                None => {
+                    configs.insert(id.clone(), saved.clone());
+                }
+            }
+        }
+    }
+
+    if !persisted.presets.is_empty() {
+        let mut presets = overlay_state.presets.lock().map_err(|e| e.to_string())?;
+        *presets = persisted.presets;
+    }
+
+    if let Some(app_persistence) = app.try_state::<PersistenceState>() {
+        *app_persistence.flags.lock().map_err(|e| e.to_string())? = flags;
+    }
+
+    Ok(())
+}
+
+/// Debounce a write to disk: rapid successive calls (e.g. dragging an
+/// overlay) collapse into a single write `SAVE_DEBOUNCE` after the last one.
+pub fn schedule_save(app: AppHandle) {
+    let generation = {
+        let persistence = app.state::<PersistenceState>();
+        persistence.generation.fetch_add(1, Ordering::SeqCst) + 1
+    };
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+
+        let persistence = app.state::<PersistenceState>();
+        if persistence.generation.load(Ordering::SeqCst) != generation {
+            // A newer change landed while we were waiting; it will save instead.
+            return;
+        }
+
+        let flags = *persistence.flags.lock().unwrap();
+        let overlay_state = app.state::<OverlayState>();
+        if let Err(err) = write_state(&app, &overlay_state, flags) {
+            eprintln!("failed to persist overlay state: {err}");
+        }
+    });
+}
+
+/// Persist the current overlay configs/presets immediately and remember
+/// which fields should be restored on the next launch.
+#[tauri::command]
+pub fn save_overlay_state(
+    app: AppHandle,
+    state: tauri::State<'_, OverlayState>,
+    persistence: tauri::State<'_, PersistenceState>,
+    flags: u32,
+) -> Result<(), String> {
+    let flags = StateFlags::from_bits_truncate(flags);
+    *persistence.flags.lock().map_err(|e| e.to_string())? = flags;
+    write_state(&app, &state, flags)
+}